@@ -16,8 +16,13 @@ use chrono::NaiveDate;
 use reqwest::StatusCode;
 use serde::Deserialize;
 use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+mod streaming;
+pub use streaming::{RealTimeTick, StreamChannel};
+
 #[derive(Deserialize, Debug)]
 pub struct RealTimeQuote {
     /// Ticker name
@@ -48,6 +53,38 @@ pub struct HistoricQuote {
     pub volume: Option<usize>,
 }
 
+/// Bar size for [`EodHistConnector::get_intraday_history`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl Interval {
+    fn as_str(self) -> &'static str {
+        match self {
+            Interval::OneMinute => "1m",
+            Interval::FiveMinutes => "5m",
+            Interval::OneHour => "1h",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IntradayQuote {
+    /// Quote datetime as string using the format `%Y-%m-%d %H:%M:%S`
+    pub datetime: String,
+    /// UNIX timestamp convention, seconds passed sind 1st January 1970
+    pub timestamp: u64,
+    pub gmtoffset: i32,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: usize,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Dividend {
@@ -62,19 +99,253 @@ pub struct Dividend {
     pub value: f64,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct Split {
+    /// Quote date as string using the format `%Y-%m-%d`
+    pub date: String,
+    /// Split ratio as `numerator/denominator`, e.g. `"4.000000/1.000000"`
+    pub split: String,
+}
+
+impl Split {
+    /// Parse the `split` field into a `(numerator, denominator)` pair, if it is well-formed
+    pub fn ratio(&self) -> Option<(f64, f64)> {
+        let mut parts = self.split.split('/');
+        let numerator = parts.next()?.parse().ok()?;
+        let denominator = parts.next()?.parse().ok()?;
+        Some((numerator, denominator))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Sentiment {
+    pub polarity: f64,
+    pub neg: f64,
+    pub neu: f64,
+    pub pos: f64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NewsArticle {
+    /// Quote date as string using the format `%Y-%m-%d %H:%M:%S`
+    pub date: String,
+    pub title: String,
+    pub content: String,
+    pub link: String,
+    pub symbols: Vec<String>,
+    pub tags: Vec<String>,
+    pub sentiment: Sentiment,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EconomicEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub country: String,
+    /// Quote date as string using the format `%Y-%m-%d %H:%M:%S`
+    pub date: String,
+    pub actual: Option<f64>,
+    pub previous: Option<f64>,
+    pub estimate: Option<f64>,
+    pub change: Option<f64>,
+}
+
+/// Body of the JSON error eodhistoricaldata returns alongside non-2xx responses
+#[derive(Deserialize, Debug)]
+struct ApiErrorBody {
+    #[serde(default)]
+    code: Option<u16>,
+    message: String,
+}
+
 #[derive(Error, Debug)]
 pub enum EodHistDataError {
     #[error("fetching the data from eodhistoricaldata failed with status code {0}")]
     FetchFailed(StatusCode),
+    #[error("eodhistoricaldata returned an error ({status}, code {code:?}): {message}")]
+    ApiError {
+        status: StatusCode,
+        code: Option<u16>,
+        message: String,
+    },
+    #[error("authentication with eodhistoricaldata failed, check your API token")]
+    Unauthorized,
     #[error("deserializing response from eodhistoricaldata failed")]
     DeserializeFailed(#[from] reqwest::Error),
     #[error("connection to eodhistoricaldata server failed")]
     ConnectionFailed(#[from] serde_json::Error),
+    #[error("streaming connection to eodhistoricaldata failed")]
+    StreamingFailed(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("rate limit exceeded, resets in {reset_in:?}")]
+    RateLimitExceeded { reset_in: Duration },
+}
+
+/// Sort order for date-ranged endpoints, mapping to the API's `order=a|d` parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+impl Order {
+    fn as_str(self) -> &'static str {
+        match self {
+            Order::Ascending => "a",
+            Order::Descending => "d",
+        }
+    }
+}
+
+/// Bar period for [`EodHistConnector::get_quote_history`], mapping to the API's `period=d|w|m` parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Period {
+    fn as_str(self) -> &'static str {
+        match self {
+            Period::Daily => "d",
+            Period::Weekly => "w",
+            Period::Monthly => "m",
+        }
+    }
+}
+
+/// Parameters for [`EodHistConnector::get_quote_history`], assembled via
+/// [`QuoteHistoryRequest::builder`]. Fields left unset simply omit their query parameter.
+#[derive(Debug, Clone)]
+pub struct QuoteHistoryRequest {
+    ticker: String,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    period: Option<Period>,
+    order: Option<Order>,
+}
+
+impl QuoteHistoryRequest {
+    /// Start building a request for `ticker`
+    pub fn builder(ticker: &str) -> QuoteHistoryRequestBuilder {
+        QuoteHistoryRequestBuilder {
+            ticker: ticker.to_string(),
+            from: None,
+            to: None,
+            period: None,
+            order: None,
+        }
+    }
+
+    fn query_params(&self) -> String {
+        let mut params = String::new();
+        if let Some(from) = self.from {
+            params.push_str(&format!("&from={}", from.format("%Y-%m-%d")));
+        }
+        if let Some(to) = self.to {
+            params.push_str(&format!("&to={}", to.format("%Y-%m-%d")));
+        }
+        if let Some(period) = self.period {
+            params.push_str(&format!("&period={}", period.as_str()));
+        }
+        if let Some(order) = self.order {
+            params.push_str(&format!("&order={}", order.as_str()));
+        }
+        params
+    }
+}
+
+/// Builder for [`QuoteHistoryRequest`]
+#[derive(Debug, Clone)]
+pub struct QuoteHistoryRequestBuilder {
+    ticker: String,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    period: Option<Period>,
+    order: Option<Order>,
+}
+
+impl QuoteHistoryRequestBuilder {
+    pub fn from(mut self, from: NaiveDate) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn to(mut self, to: NaiveDate) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    pub fn period(mut self, period: Period) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    pub fn build(self) -> QuoteHistoryRequest {
+        QuoteHistoryRequest {
+            ticker: self.ticker,
+            from: self.from,
+            to: self.to,
+            period: self.period,
+            order: self.order,
+        }
+    }
+}
+
+/// Token-bucket rate limiter tracking requests consumed against a rolling 24h window
+struct RateLimiter {
+    max_per_day: u32,
+    window: Duration,
+    window_start: Instant,
+    consumed: u32,
+}
+
+impl RateLimiter {
+    fn new(max_per_day: u32) -> Self {
+        Self::with_window(max_per_day, Duration::from_secs(24 * 60 * 60), Instant::now())
+    }
+
+    /// Like [`RateLimiter::new`], but with an explicit window length and start instant so the
+    /// exhaustion/reset behaviour can be exercised deterministically in tests.
+    fn with_window(max_per_day: u32, window: Duration, window_start: Instant) -> Self {
+        RateLimiter {
+            max_per_day,
+            window,
+            window_start,
+            consumed: 0,
+        }
+    }
+
+    /// Consume one request from the bucket, or return the time left until the window resets
+    fn try_consume(&mut self) -> Result<(), Duration> {
+        self.try_consume_at(Instant::now())
+    }
+
+    /// Like [`RateLimiter::try_consume`], but takes the current instant explicitly
+    fn try_consume_at(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= self.window {
+            self.window_start = now;
+            self.consumed = 0;
+        }
+        if self.consumed >= self.max_per_day {
+            return Err(self.window.saturating_sub(elapsed));
+        }
+        self.consumed += 1;
+        Ok(())
+    }
 }
 
 pub struct EodHistConnector {
     url: &'static str,
     api_token: String,
+    client: reqwest::Client,
+    rate_limiter: Option<Mutex<RateLimiter>>,
 }
 
 impl EodHistConnector {
@@ -84,6 +355,17 @@ impl EodHistConnector {
         EodHistConnector {
             url: "https://eodhistoricaldata.com/api",
             api_token: token,
+            client: reqwest::Client::new(),
+            rate_limiter: None,
+        }
+    }
+
+    /// Constructor for a new instance of EodHistConnector that enforces a token-bucket rate
+    /// limit of `max_per_day` requests per rolling 24h window, e.g. the free tier's quota of 20.
+    pub fn with_rate_limit(token: String, max_per_day: u32) -> EodHistConnector {
+        EodHistConnector {
+            rate_limiter: Some(Mutex::new(RateLimiter::new(max_per_day))),
+            ..EodHistConnector::new(token)
         }
     }
 
@@ -98,23 +380,46 @@ impl EodHistConnector {
         Ok(quote)
     }
 
-    /// Retrieve the quote history for the given ticker form date start to end (inklusive), if available
+    /// Retrieve the quote history described by `request`, built via [`QuoteHistoryRequest::builder`]
     pub async fn get_quote_history(
+        &self,
+        request: QuoteHistoryRequest,
+    ) -> Result<Vec<HistoricQuote>, EodHistDataError> {
+        let url: String = format!(
+            "{}/eod/{}?api_token={}&fmt=json{}",
+            self.url,
+            request.ticker,
+            self.api_token,
+            request.query_params()
+        );
+        let resp = self.send_request(&url).await?;
+        let quotes: Vec<HistoricQuote> = serde_json::from_value(resp)?;
+        Ok(quotes)
+    }
+
+    /// Retrieve intraday history for the given ticker form date start to end (inklusive), if available.
+    /// The eodhistoricaldata intraday endpoint expects `from`/`to` as UNIX timestamps, so `start` and
+    /// `end` are converted to the start and end of their respective day.
+    pub async fn get_intraday_history(
         &self,
         ticker: &str,
         start: NaiveDate,
         end: NaiveDate,
-    ) -> Result<Vec<HistoricQuote>, EodHistDataError> {
+        interval: Interval,
+    ) -> Result<Vec<IntradayQuote>, EodHistDataError> {
+        let from = start.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+        let to = end.and_hms_opt(23, 59, 59).unwrap().and_utc().timestamp();
         let url: String = format!(
-            "{}/eod/{}?from={}&to={}&api_token={}&period=d&fmt=json",
+            "{}/intraday/{}?from={}&to={}&interval={}&api_token={}&fmt=json",
             self.url,
             ticker,
-            start.format("%Y-%m-%d"),
-            end.format("%Y-%m-%d"),
+            from,
+            to,
+            interval.as_str(),
             self.api_token
         );
         let resp = self.send_request(&url).await?;
-        let quotes: Vec<HistoricQuote> = serde_json::from_value(resp)?;
+        let quotes: Vec<IntradayQuote> = serde_json::from_value(resp)?;
         Ok(quotes)
     }
 
@@ -136,21 +441,162 @@ impl EodHistConnector {
         Ok(dividends)
     }
 
+    /// Retrieve the split history for the given ticker form date start to end (inklusive), if available
+    pub async fn get_split_history(
+        &self,
+        ticker: &str,
+        start: NaiveDate,
+    ) -> Result<Vec<Split>, EodHistDataError> {
+        let url: String = format!(
+            "{}/splits/{}?from={}&api_token={}&fmt=json",
+            self.url,
+            ticker,
+            start.format("%Y-%m-%d"),
+            self.api_token
+        );
+        let resp = self.send_request(&url).await?;
+        let splits: Vec<Split> = serde_json::from_value(resp)?;
+        Ok(splits)
+    }
+
+    /// Retrieve news articles with per-ticker sentiment scores form date from to to (inklusive), if available
+    pub async fn get_news(
+        &self,
+        ticker: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: u32,
+    ) -> Result<Vec<NewsArticle>, EodHistDataError> {
+        let url: String = format!(
+            "{}/news?s={}&from={}&to={}&limit={}&api_token={}&fmt=json",
+            self.url,
+            ticker,
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d"),
+            limit,
+            self.api_token
+        );
+        let resp = self.send_request(&url).await?;
+        let articles: Vec<NewsArticle> = serde_json::from_value(resp)?;
+        Ok(articles)
+    }
+
+    /// Retrieve economic calendar events form date from to to (inklusive), if available.
+    /// `country` and `comparison` (e.g. `mom`/`qoq`/`yoy`) are omitted from the query when `None`.
+    pub async fn get_economic_events(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        country: Option<&str>,
+        comparison: Option<&str>,
+    ) -> Result<Vec<EconomicEvent>, EodHistDataError> {
+        let mut url: String = format!(
+            "{}/economic-events?from={}&to={}&api_token={}&fmt=json",
+            self.url,
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d"),
+            self.api_token
+        );
+        if let Some(country) = country {
+            url.push_str(&format!("&country={}", country));
+        }
+        if let Some(comparison) = comparison {
+            url.push_str(&format!("&comparison={}", comparison));
+        }
+        let resp = self.send_request(&url).await?;
+        let events: Vec<EconomicEvent> = serde_json::from_value(resp)?;
+        Ok(events)
+    }
+
     /// Send request to eodhistoricaldata server and transform response to JSON value
     async fn send_request(&self, url: &str) -> Result<Value, EodHistDataError> {
-        let resp = reqwest::get(url).await?;
-        match resp.status() {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if let Err(reset_in) = rate_limiter.lock().unwrap().try_consume() {
+                return Err(EodHistDataError::RateLimitExceeded { reset_in });
+            }
+        }
+        let resp = self.client.get(url).send().await?;
+        let status = resp.status();
+        match status {
             StatusCode::OK => Ok(resp.json().await?),
-            status => Err(EodHistDataError::FetchFailed(status)),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(EodHistDataError::Unauthorized),
+            status => {
+                let body = resp.text().await.unwrap_or_default();
+                Err(map_error_body(status, &body))
+            }
         }
     }
 }
 
+/// Turn a non-200, non-401/403 response body into an error, parsing it as an [`ApiErrorBody`]
+/// where possible and falling back to the bare status code otherwise.
+fn map_error_body(status: StatusCode, body: &str) -> EodHistDataError {
+    match serde_json::from_str::<ApiErrorBody>(body) {
+        Ok(err) => EodHistDataError::ApiError {
+            status,
+            code: err.code,
+            message: err.message,
+        },
+        Err(_) => EodHistDataError::FetchFailed(status),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tokio_test;
 
+    #[test]
+    fn test_map_error_body_parses_api_error() {
+        let body = r#"{"code":401,"message":"Invalid ticker"}"#;
+        let err = map_error_body(StatusCode::BAD_REQUEST, body);
+
+        match err {
+            EodHistDataError::ApiError {
+                status,
+                code,
+                message,
+            } => {
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(code, Some(401));
+                assert_eq!(message, "Invalid ticker");
+            }
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_map_error_body_falls_back_on_unparsable_body() {
+        let err = map_error_body(StatusCode::INTERNAL_SERVER_ERROR, "not json");
+
+        match err {
+            EodHistDataError::FetchFailed(status) => {
+                assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            other => panic!("expected FetchFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_exhaustion_and_reset() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::with_window(2, Duration::from_secs(60), start);
+
+        assert!(limiter.try_consume_at(start).is_ok());
+        assert!(limiter
+            .try_consume_at(start + Duration::from_secs(10))
+            .is_ok());
+        // bucket is exhausted for the remainder of the window
+        assert!(limiter
+            .try_consume_at(start + Duration::from_secs(20))
+            .is_err());
+
+        // once the window has elapsed the bucket refills
+        assert!(limiter
+            .try_consume_at(start + Duration::from_secs(61))
+            .is_ok());
+    }
+
     #[test]
     fn test_get_single_quote() {
         // Use the official test token
@@ -168,14 +614,37 @@ mod tests {
         let provider = EodHistConnector::new(token);
         let start = NaiveDate::from_ymd_opt(2020, 01, 01).unwrap();
         let end = NaiveDate::from_ymd_opt(2020, 01, 31).unwrap();
-        let quotes =
-            tokio_test::block_on(provider.get_quote_history("AAPL.US", start, end)).unwrap();
+        let request = QuoteHistoryRequest::builder("AAPL.US")
+            .from(start)
+            .to(end)
+            .period(Period::Daily)
+            .order(Order::Ascending)
+            .build();
+        let quotes = tokio_test::block_on(provider.get_quote_history(request)).unwrap();
 
         assert_eq!(quotes.len(), 21);
         assert_eq!(quotes[0].date, "2020-01-02");
         assert_eq!(quotes[quotes.len() - 1].date, "2020-01-31");
     }
 
+    #[test]
+    fn test_get_intraday_history() {
+        // Use the official test token
+        let token = "OeAFFmMliFG5orCUuwAKQ8l4WWFQ67YX".to_string();
+        let provider = EodHistConnector::new(token);
+        let start = NaiveDate::from_ymd_opt(2020, 01, 01).unwrap();
+        let end = NaiveDate::from_ymd_opt(2020, 01, 31).unwrap();
+        let quotes = tokio_test::block_on(provider.get_intraday_history(
+            "AAPL.US",
+            start,
+            end,
+            Interval::OneHour,
+        ))
+        .unwrap();
+
+        assert!(!quotes.is_empty());
+    }
+
     #[test]
     fn test_get_dividend_history() {
         // Use the official test token
@@ -187,4 +656,47 @@ mod tests {
 
         assert!(dividends.len() >= 4);
     }
+
+    #[test]
+    fn test_get_split_history() {
+        // Use the official test token
+        let token = "OeAFFmMliFG5orCUuwAKQ8l4WWFQ67YX".to_string();
+        let provider = EodHistConnector::new(token);
+        let start = NaiveDate::from_ymd_opt(2020, 01, 01).unwrap();
+        let splits = tokio_test::block_on(provider.get_split_history("AAPL.US", start)).unwrap();
+
+        assert!(!splits.is_empty());
+        assert!(splits[0].ratio().is_some());
+    }
+
+    #[test]
+    fn test_get_news() {
+        // Use the official test token
+        let token = "OeAFFmMliFG5orCUuwAKQ8l4WWFQ67YX".to_string();
+        let provider = EodHistConnector::new(token);
+        let from = NaiveDate::from_ymd_opt(2020, 01, 01).unwrap();
+        let to = NaiveDate::from_ymd_opt(2020, 01, 31).unwrap();
+        let articles =
+            tokio_test::block_on(provider.get_news("AAPL.US", from, to, 10)).unwrap();
+
+        assert!(!articles.is_empty());
+    }
+
+    #[test]
+    fn test_get_economic_events() {
+        // Use the official test token
+        let token = "OeAFFmMliFG5orCUuwAKQ8l4WWFQ67YX".to_string();
+        let provider = EodHistConnector::new(token);
+        let from = NaiveDate::from_ymd_opt(2020, 01, 01).unwrap();
+        let to = NaiveDate::from_ymd_opt(2020, 01, 31).unwrap();
+        let events = tokio_test::block_on(provider.get_economic_events(
+            from,
+            to,
+            Some("US"),
+            Some("mom"),
+        ))
+        .unwrap();
+
+        assert!(!events.is_empty());
+    }
 }
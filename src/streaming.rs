@@ -0,0 +1,186 @@
+//! Real-time tick streaming over the eodhistoricaldata WebSocket API.
+//!
+//! Unlike [`EodHistConnector::get_latest_quote`](crate::EodHistConnector::get_latest_quote),
+//! which polls a single snapshot, [`EodHistConnector::subscribe_quotes`] opens a persistent
+//! WebSocket connection and yields ticks as they arrive, reconnecting (and resubscribing)
+//! automatically if the connection drops.
+
+use crate::{EodHistConnector, EodHistDataError};
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// A live tick endpoint on the eodhistoricaldata WebSocket API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamChannel {
+    /// US stock quotes (`us-quote`)
+    UsQuote,
+    /// US stock trades (`us`)
+    UsTrade,
+    /// Forex pairs (`forex`)
+    Forex,
+    /// Crypto pairs (`crypto`)
+    Crypto,
+}
+
+impl StreamChannel {
+    fn path(self) -> &'static str {
+        match self {
+            StreamChannel::UsQuote => "us-quote",
+            StreamChannel::UsTrade => "us",
+            StreamChannel::Forex => "forex",
+            StreamChannel::Crypto => "crypto",
+        }
+    }
+}
+
+/// A single live tick received over a [`StreamChannel`].
+#[derive(Deserialize, Debug)]
+pub struct RealTimeTick {
+    /// Ticker symbol this tick belongs to
+    #[serde(rename = "s")]
+    pub code: String,
+    /// Last traded/quoted price
+    #[serde(rename = "p")]
+    pub price: f64,
+    /// Trade or quote size
+    #[serde(rename = "v")]
+    pub size: f64,
+    /// UNIX timestamp in milliseconds
+    #[serde(rename = "t")]
+    pub timestamp: u64,
+    /// Exchange the tick originated from, if reported
+    #[serde(rename = "dc", default)]
+    pub exchange: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SubscribeMessage {
+    action: &'static str,
+    symbols: String,
+}
+
+/// Parse a single WebSocket text frame into a tick, ignoring frames that aren't ticks (e.g. the
+/// `{"status_code":200,"message":"Authorized"}` acknowledgement sent right after connecting).
+fn parse_tick(text: &str) -> Option<RealTimeTick> {
+    serde_json::from_str(text).ok()
+}
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Number of consecutive connect/subscribe/socket failures tolerated silently before the stream
+/// surfaces an `Err` item to the caller. Keeps a single transient blip quiet while still letting
+/// callers detect a bad token or a permanently unreachable host.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+impl EodHistConnector {
+    /// Open a streaming connection to `channel` and subscribe to `tickers`, returning a
+    /// stream of live ticks. The connection is re-established and resubscribed
+    /// automatically if it drops or fails to connect; after a few consecutive failures the
+    /// stream yields an `Err` before continuing to retry.
+    pub fn subscribe_quotes<'a>(
+        &'a self,
+        channel: StreamChannel,
+        tickers: &[&str],
+    ) -> impl Stream<Item = Result<RealTimeTick, EodHistDataError>> + 'a {
+        let symbols = tickers.join(",");
+        async_stream::stream! {
+            let mut consecutive_failures: u32 = 0;
+            loop {
+                let ws_url = format!(
+                    "wss://ws.eodhistoricaldata.com/ws/{}?api_token={}",
+                    channel.path(),
+                    self.api_token
+                );
+                let mut socket = match connect_async(&ws_url).await {
+                    Ok((socket, _)) => socket,
+                    Err(e) => {
+                        consecutive_failures += 1;
+                        if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                            consecutive_failures = 0;
+                            yield Err(EodHistDataError::StreamingFailed(e));
+                        }
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let subscribe = SubscribeMessage {
+                    action: "subscribe",
+                    symbols: symbols.clone(),
+                };
+                // Serializing a plain struct of strings cannot fail.
+                let payload = serde_json::to_string(&subscribe).unwrap();
+                if let Err(e) = socket.send(Message::Text(payload)).await {
+                    consecutive_failures += 1;
+                    if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                        consecutive_failures = 0;
+                        yield Err(EodHistDataError::StreamingFailed(e));
+                    }
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+                consecutive_failures = 0;
+
+                let mut socket_error = None;
+                while let Some(msg) = socket.next().await {
+                    match msg {
+                        Ok(Message::Text(text)) => {
+                            if let Some(tick) = parse_tick(&text) {
+                                yield Ok(tick);
+                            }
+                        }
+                        Ok(Message::Close(_)) => break,
+                        Err(e) => {
+                            socket_error = Some(e);
+                            break;
+                        }
+                        _ => continue,
+                    }
+                }
+
+                if let Some(e) = socket_error {
+                    consecutive_failures += 1;
+                    if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                        consecutive_failures = 0;
+                        yield Err(EodHistDataError::StreamingFailed(e));
+                    }
+                }
+
+                let unsubscribe = SubscribeMessage {
+                    action: "unsubscribe",
+                    symbols: symbols.clone(),
+                };
+                let payload = serde_json::to_string(&unsubscribe).unwrap();
+                // Best-effort: the socket may already be gone, nothing to do if this fails.
+                let _ = socket.send(Message::Text(payload)).await;
+
+                // Back off briefly before reconnecting and resubscribing.
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tick() {
+        let text = r#"{"s":"AAPL.US","p":150.25,"v":100.0,"t":1700000000000,"dc":"NASDAQ"}"#;
+        let tick = parse_tick(text).unwrap();
+
+        assert_eq!(tick.code, "AAPL.US");
+        assert_eq!(tick.price, 150.25);
+        assert_eq!(tick.exchange.as_deref(), Some("NASDAQ"));
+    }
+
+    #[test]
+    fn test_parse_tick_ignores_non_tick_frames() {
+        // The server sends a status acknowledgement right after connecting, before any ticks.
+        let text = r#"{"status_code":200,"message":"Authorized"}"#;
+
+        assert!(parse_tick(text).is_none());
+    }
+}